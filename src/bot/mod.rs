@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use teloxide::dispatching::UpdateFilterExt;
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+use teloxide::utils::command::BotCommands;
+use tracing::{error, info};
+
+use crate::config::NotifyTarget;
+use crate::routes::RouteStore;
+
+#[derive(BotCommands, Clone)]
+#[command(rename_rule = "lowercase", description = "Manage live routing subscriptions:")]
+enum Command {
+    #[command(description = "subscribe a repo to events: /subscribe <repo_pattern> <event...>")]
+    Subscribe(String),
+    #[command(description = "remove a subscription: /unsubscribe <repo_pattern>")]
+    Unsubscribe(String),
+    #[command(description = "list active subscriptions")]
+    Routes,
+    #[command(description = "mute a repo: /mute <repo_pattern>")]
+    Mute(String),
+}
+
+/// Runs the optional control bot that lets `admin_chat_id`/`admin_handle` manage
+/// `RouteStore` subscriptions without restarting the service. `webhook::handle_webhook`
+/// re-reads `store` on every incoming webhook, so changes here take effect immediately.
+pub async fn run_bot(
+    bot_token: String,
+    admin_chat_id: Option<i64>,
+    admin_handle: Option<String>,
+    store: Arc<RouteStore>,
+) {
+    let bot = Bot::new(bot_token);
+    let tap_store = store.clone();
+
+    let commands = Update::filter_message().filter_command::<Command>().endpoint(
+        move |bot: Bot, msg: Message, cmd: Command| {
+            let store = store.clone();
+            let admin_handle = admin_handle.clone();
+            async move {
+                if is_admin(&msg, admin_chat_id, admin_handle.as_deref()) {
+                    let reply = handle_command(&store, msg.chat.id.0, cmd).await;
+                    bot.send_message(msg.chat.id, reply).await?;
+                }
+                respond(())
+            }
+        },
+    );
+
+    let subscribe_taps = Update::filter_callback_query().endpoint(
+        move |bot: Bot, query: CallbackQuery| {
+            let store = tap_store.clone();
+            async move {
+                if let Err(e) = handle_subscribe_tap(&bot, &store, &query).await {
+                    error!(error = %e, "Failed to handle subscribe tap");
+                }
+                respond(())
+            }
+        },
+    );
+
+    info!("Starting Telegram control bot");
+    Dispatcher::builder(bot, dptree::entry().branch(commands).branch(subscribe_taps))
+        .build()
+        .dispatch()
+        .await;
+}
+
+fn is_admin(msg: &Message, admin_chat_id: Option<i64>, admin_handle: Option<&str>) -> bool {
+    if admin_chat_id == Some(msg.chat.id.0) {
+        return true;
+    }
+    match (admin_handle, msg.from().and_then(|u| u.username.as_deref())) {
+        (Some(expected), Some(actual)) => expected.eq_ignore_ascii_case(actual),
+        _ => false,
+    }
+}
+
+async fn handle_command(store: &RouteStore, chat_id: i64, cmd: Command) -> String {
+    match cmd {
+        Command::Subscribe(args) => {
+            let mut parts = args.split_whitespace();
+            let Some(repo_pattern) = parts.next() else {
+                return "Usage: /subscribe <repo_pattern> <event...>".to_string();
+            };
+            let events: Vec<String> = parts.map(String::from).collect();
+            let events = if events.is_empty() {
+                vec!["*".to_string()]
+            } else {
+                events
+            };
+            let target = NotifyTarget::Telegram {
+                chat_id,
+                parse_mode: None,
+            };
+            match store.subscribe(repo_pattern, &events, &target).await {
+                Ok(()) => format!(
+                    "Subscribed to `{repo_pattern}` for events: {}",
+                    events.join(", ")
+                ),
+                Err(e) => format!("Failed to subscribe: {e}"),
+            }
+        }
+        Command::Unsubscribe(repo_pattern) => {
+            let repo_pattern = repo_pattern.trim();
+            if repo_pattern.is_empty() {
+                return "Usage: /unsubscribe <repo_pattern>".to_string();
+            }
+            match store.unsubscribe(repo_pattern).await {
+                Ok(0) => format!("No subscription found for `{repo_pattern}`."),
+                Ok(n) => format!("Removed {n} subscription(s) for `{repo_pattern}`."),
+                Err(e) => format!("Failed to unsubscribe: {e}"),
+            }
+        }
+        Command::Routes => match store.list().await {
+            Ok(routes) if routes.is_empty() => "No active subscriptions.".to_string(),
+            Ok(routes) => routes
+                .iter()
+                .map(|r| {
+                    format!(
+                        "- `{}` -> {} [{}]",
+                        r.repo_pattern,
+                        r.target.kind(),
+                        r.events.join(", ")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Err(e) => format!("Failed to list subscriptions: {e}"),
+        },
+        Command::Mute(repo_pattern) => {
+            let repo_pattern = repo_pattern.trim();
+            if repo_pattern.is_empty() {
+                return "Usage: /mute <repo_pattern>".to_string();
+            }
+            match store.mute(repo_pattern).await {
+                Ok(0) => format!("No subscription found for `{repo_pattern}`."),
+                Ok(n) => format!("Muted {n} subscription(s) for `{repo_pattern}`."),
+                Err(e) => format!("Failed to mute: {e}"),
+            }
+        }
+    }
+}
+
+async fn handle_subscribe_tap(
+    bot: &Bot,
+    store: &RouteStore,
+    query: &CallbackQuery,
+) -> anyhow::Result<()> {
+    let Some(repo_pattern) = query.data.as_deref().and_then(|d| d.strip_prefix("subscribe:"))
+    else {
+        return Ok(());
+    };
+    let Some(message) = &query.message else {
+        return Ok(());
+    };
+
+    // The button only ever reaches `admin_chat_id` (it's sent there by `AdminNotifier`),
+    // so tapping it is as trustworthy as the DM itself was.
+    let chat_id = message.chat().id;
+    let target = NotifyTarget::Telegram {
+        chat_id: chat_id.0,
+        parse_mode: None,
+    };
+    let reply = match store.subscribe(repo_pattern, &["*".to_string()], &target).await {
+        Ok(()) => format!("Subscribed to `{repo_pattern}` for all events."),
+        Err(e) => format!("Failed to subscribe: {e}"),
+    };
+
+    bot.answer_callback_query(query.id.clone()).await?;
+    bot.send_message(chat_id, reply).await?;
+    Ok(())
+}
+
+/// Builds the "new repo seen" DM, backed by a `subscribe:<repo>` inline button.
+#[derive(Clone)]
+pub struct AdminNotifier {
+    bot: Bot,
+    admin_chat_id: i64,
+}
+
+impl AdminNotifier {
+    pub fn new(bot_token: String, admin_chat_id: i64) -> Self {
+        Self {
+            bot: Bot::new(bot_token),
+            admin_chat_id,
+        }
+    }
+
+    /// DMs the admin a one-tap prompt when `repo_full_name` matched no route at all.
+    pub async fn prompt_subscribe(&self, repo_full_name: &str) -> anyhow::Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+            "Subscribe to all events",
+            format!("subscribe:{repo_full_name}"),
+        )]]);
+
+        self.bot
+            .send_message(
+                ChatId(self.admin_chat_id),
+                format!("New repo seen: `{repo_full_name}`. No route matched it. Subscribe?"),
+            )
+            .reply_markup(keyboard)
+            .await?;
+        Ok(())
+    }
+}
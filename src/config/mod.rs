@@ -1,10 +1,18 @@
-use serde::Deserialize;
+pub mod patterns;
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::telegram::ParseMode;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Settings {
     pub server: ServerConfig,
     pub telegram: TelegramConfig,
     pub github: GitHubConfig,
+    #[serde(default)]
+    pub queue: QueueConfig,
     pub routing: Vec<RouteConfig>,
 }
 
@@ -14,21 +22,137 @@ pub struct ServerConfig {
     pub port: u16,
 }
 
+fn default_parse_mode() -> ParseMode {
+    ParseMode::Markdown
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct TelegramConfig {
     pub bot_token: String,
+    #[serde(default = "default_parse_mode")]
+    pub parse_mode: ParseMode,
+    /// Templates keyed by event key (e.g. `pull_request.opened`, `push`), used when a
+    /// route doesn't supply its own. See `telegram::template` for the placeholder syntax.
+    #[serde(default)]
+    pub templates: HashMap<String, String>,
+    /// Chat id allowed to issue `/subscribe`, `/unsubscribe`, `/routes` and `/mute`
+    /// commands to the optional control bot (see `crate::bot`). The bot only starts if
+    /// this or `admin_handle` is set.
+    #[serde(default)]
+    pub admin_chat_id: Option<i64>,
+    /// Telegram @handle (without the `@`) allowed to issue control-bot commands, checked
+    /// for senders that don't match `admin_chat_id` (e.g. the admin DMing from a device
+    /// with a different chat id).
+    #[serde(default)]
+    pub admin_handle: Option<String>,
+}
+
+fn default_queue_db_path() -> String {
+    "notifications.db".to_string()
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct QueueConfig {
+    #[serde(default = "default_queue_db_path")]
+    pub db_path: String,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            db_path: default_queue_db_path(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum WebhookSecrets {
+    Single(String),
+    Many(Vec<String>),
+}
+
+impl WebhookSecrets {
+    pub fn as_slice(&self) -> Vec<&str> {
+        match self {
+            WebhookSecrets::Single(s) => vec![s.as_str()],
+            WebhookSecrets::Many(list) => list.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+fn default_push_commit_limit() -> usize {
+    10
+}
+
+fn default_enrich_cache_ttl_secs() -> u64 {
+    300
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct GitHubConfig {
-    pub webhook_secret: String,
+    pub webhook_secret: WebhookSecrets,
+    /// Max commit lines to render for a push event before collapsing the rest into
+    /// an "...and N more" line.
+    #[serde(default = "default_push_commit_limit")]
+    pub push_commit_limit: usize,
+    /// Token used by `github::api` to enrich events with extra API fields. Required only
+    /// for routes with `enrich: true`.
+    pub api_token: Option<String>,
+    /// How long `github::api`'s cache keeps an entry fresh before revalidating it.
+    #[serde(default = "default_enrich_cache_ttl_secs")]
+    pub enrich_cache_ttl_secs: u64,
+}
+
+/// Where a matched event gets delivered. The `type` tag selects the notifier backend
+/// that `notifier::Registry` dispatches to.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotifyTarget {
+    Telegram {
+        chat_id: i64,
+        /// The resolved `parse_mode` this delivery should use, threaded through by
+        /// `notifier::resolve_deliveries` from the matched route (falling back to the
+        /// registry's configured default if unset). Never set from `config` directly —
+        /// routes carry their override in `RouteConfig.parse_mode` instead.
+        #[serde(default)]
+        parse_mode: Option<ParseMode>,
+    },
+    Slack {
+        webhook_url: String,
+        channel: Option<String>,
+    },
+    Discord {
+        webhook_url: String,
+    },
+    Generic {
+        url: String,
+    },
+}
+
+impl NotifyTarget {
+    /// The registry key of the notifier backend this target dispatches to.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            NotifyTarget::Telegram { .. } => "telegram",
+            NotifyTarget::Slack { .. } => "slack",
+            NotifyTarget::Discord { .. } => "discord",
+            NotifyTarget::Generic { .. } => "generic",
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct RouteConfig {
     pub repo_pattern: String,
-    pub chat_id: i64,
+    pub target: NotifyTarget,
     pub events: Vec<String>,
+    pub parse_mode: Option<ParseMode>,
+    #[serde(default)]
+    pub templates: HashMap<String, String>,
+    /// Fetch extra fields from the GitHub API (via `github::api`) before formatting.
+    #[serde(default)]
+    pub enrich: bool,
 }
 
 impl Settings {
@@ -38,6 +162,20 @@ impl Settings {
             .add_source(config::Environment::with_prefix("APP"))
             .build()?;
 
-        settings.try_deserialize().map_err(|e| anyhow::anyhow!(e))
+        let settings: Self = settings.try_deserialize().map_err(|e| anyhow::anyhow!(e))?;
+        settings.validate_routing()?;
+        Ok(settings)
+    }
+
+    /// Fails fast on an invalid `repo_pattern`/`events` entry instead of discovering it
+    /// the first time a webhook tries to route against it.
+    fn validate_routing(&self) -> anyhow::Result<()> {
+        for route in &self.routing {
+            patterns::compile_repo_pattern(&route.repo_pattern)?;
+            for event in &route.events {
+                patterns::compile_event_pattern(event)?;
+            }
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,35 @@
+use regex::Regex;
+
+/// Strips an optional leading `!` (negation) and returns the remaining pattern body.
+fn split_negation(pattern: &str) -> (bool, &str) {
+    match pattern.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, pattern),
+    }
+}
+
+/// Compiles a `repo_pattern` into an anchored regex plus whether it was negated with a
+/// leading `!`. The pattern is taken as a real regex (e.g. `org/(api|web)-.*`); a bare
+/// `*` is shorthand for "match anything".
+pub fn compile_repo_pattern(pattern: &str) -> anyhow::Result<(Regex, bool)> {
+    let (negate, body) = split_negation(pattern);
+    let body = if body == "*" { ".*" } else { body };
+
+    let regex = Regex::new(&format!("^(?:{body})$"))
+        .map_err(|e| anyhow::anyhow!("invalid repo_pattern `{}`: {}", pattern, e))?;
+
+    Ok((regex, negate))
+}
+
+/// Compiles an `events` entry into an anchored regex plus whether it was negated with a
+/// leading `!`. Unlike `repo_pattern`, this is a glob, not a full regex: only `*` is
+/// special (translated to `.*`), so `pull_request.*` matches any `pull_request` action.
+pub fn compile_event_pattern(pattern: &str) -> anyhow::Result<(Regex, bool)> {
+    let (negate, body) = split_negation(pattern);
+    let translated = regex::escape(body).replace("\\*", ".*");
+
+    let regex = Regex::new(&format!("^(?:{translated})$"))
+        .map_err(|e| anyhow::anyhow!("invalid events pattern `{}`: {}", pattern, e))?;
+
+    Ok((regex, negate))
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use reqwest::Client;
+use serde_json::Value;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+struct CacheEntry {
+    value: Value,
+    etag: Option<String>,
+    expires_at: Instant,
+}
+
+/// Thin GitHub REST API client with an in-memory per-URL TTL cache, used to enrich
+/// webhook payloads with fields they don't carry (PR diffstat, labels, release assets).
+/// Cached entries are revalidated with `If-None-Match` so a 304 doesn't cost rate limit.
+pub struct ApiClient {
+    client: Client,
+    token: Option<String>,
+    ttl: Duration,
+    cache: Mutex<HashMap<String, CacheEntry>>,
+}
+
+impl ApiClient {
+    pub fn new(token: Option<String>, ttl: Duration) -> Self {
+        Self {
+            client: Client::new(),
+            token,
+            ttl,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get_json(&self, url: &str) -> anyhow::Result<Value> {
+        let cached_etag = {
+            let cache = self.cache.lock().await;
+            match cache.get(url) {
+                Some(entry) if entry.expires_at > Instant::now() => {
+                    return Ok(entry.value.clone());
+                }
+                Some(entry) => entry.etag.clone(),
+                None => None,
+            }
+        };
+
+        let mut request = self
+            .client
+            .get(url)
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", "telegram-github-notifications");
+
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(etag) = &cached_etag {
+            request = request.header("If-None-Match", etag.clone());
+        }
+
+        let response = request.send().await?;
+
+        if let Some(remaining) = response
+            .headers()
+            .get("x-ratelimit-remaining")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<i64>().ok())
+        {
+            if remaining <= 5 {
+                warn!(remaining, url = %url, "GitHub API rate limit running low");
+            }
+        }
+
+        if response.status().as_u16() == 304 {
+            debug!(url = %url, "GitHub API cache revalidated (304)");
+            let mut cache = self.cache.lock().await;
+            if let Some(entry) = cache.get_mut(url) {
+                entry.expires_at = Instant::now() + self.ttl;
+                return Ok(entry.value.clone());
+            }
+            anyhow::bail!("received 304 for {} with no cached entry", url);
+        }
+
+        if !response.status().is_success() {
+            anyhow::bail!("GitHub API request to {} failed: {}", url, response.status());
+        }
+
+        let etag = response
+            .headers()
+            .get("etag")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let value: Value = response.json().await?;
+
+        let mut cache = self.cache.lock().await;
+        cache.insert(
+            url.to_string(),
+            CacheEntry {
+                value: value.clone(),
+                etag,
+                expires_at: Instant::now() + self.ttl,
+            },
+        );
+
+        Ok(value)
+    }
+}
+
+/// Flattens the scalar top-level fields of `value` into `enrich.<field>` template
+/// placeholders. Nested objects and empty arrays are skipped; a non-empty array whose
+/// items are all strings or objects with a string `name` (e.g. labels, release assets)
+/// is rendered as those strings/names joined with `, `, and any other array is skipped.
+pub fn flatten_for_template(value: &Value) -> HashMap<String, String> {
+    let mut out = HashMap::new();
+    let Some(object) = value.as_object() else {
+        return out;
+    };
+
+    for (key, field) in object {
+        let rendered = match field {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            Value::Bool(b) => Some(b.to_string()),
+            Value::Array(items) => {
+                let names: Vec<String> = items
+                    .iter()
+                    .filter_map(|item| {
+                        item.as_str()
+                            .map(str::to_string)
+                            .or_else(|| item.get("name")?.as_str().map(str::to_string))
+                    })
+                    .collect();
+                if names.len() == items.len() && !items.is_empty() {
+                    Some(names.join(", "))
+                } else {
+                    None
+                }
+            }
+            Value::Object(_) | Value::Null => None,
+        };
+
+        if let Some(rendered) = rendered {
+            out.insert(format!("enrich.{key}"), rendered);
+        }
+    }
+
+    out
+}
@@ -1,7 +1,47 @@
+pub mod api;
+
 use std::collections::HashMap;
 
 use serde::{de::DeserializeOwned, Deserialize};
 
+/// Which inline markup `format_message` should emit. Telegram understands the legacy
+/// Markdown delimiters (`*bold*`, `` `code` ``, `[text](url)`), but the other notifier
+/// backends don't, so a route without a custom template still gets a readable fallback
+/// instead of literal asterisks and `[text](url)` syntax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFlavor {
+    TelegramMarkdown,
+    PlainText,
+}
+
+fn bold(flavor: MessageFlavor, s: &str) -> String {
+    match flavor {
+        MessageFlavor::TelegramMarkdown => format!("*{s}*"),
+        MessageFlavor::PlainText => s.to_string(),
+    }
+}
+
+fn italic(flavor: MessageFlavor, s: &str) -> String {
+    match flavor {
+        MessageFlavor::TelegramMarkdown => format!("_{s}_"),
+        MessageFlavor::PlainText => s.to_string(),
+    }
+}
+
+fn code(flavor: MessageFlavor, s: &str) -> String {
+    match flavor {
+        MessageFlavor::TelegramMarkdown => format!("`{s}`"),
+        MessageFlavor::PlainText => s.to_string(),
+    }
+}
+
+fn link(flavor: MessageFlavor, text: &str, url: &str) -> String {
+    match flavor {
+        MessageFlavor::TelegramMarkdown => format!("[{text}]({url})"),
+        MessageFlavor::PlainText => format!("{text} ({url})"),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GitHubEvent {
     pub event_type: String,
@@ -40,6 +80,8 @@ pub struct PullRequestPayload {
     pub number: u64,
     pub title: String,
     pub html_url: String,
+    /// The API URL for this PR (distinct from `html_url`), used by `github::api` to enrich.
+    pub url: Option<String>,
     pub state: String,
     pub merged: Option<bool>,
     pub base: BaseRef,
@@ -56,6 +98,8 @@ pub struct IssuePayload {
     pub number: u64,
     pub title: String,
     pub html_url: String,
+    /// The API URL for this issue (distinct from `html_url`), used by `github::api` to enrich.
+    pub url: Option<String>,
     pub state: String,
 }
 
@@ -65,6 +109,65 @@ pub struct PushPayload {
     pub ref_name: String,
     pub compare: String,
     pub commits: Vec<Commit>,
+    #[serde(default)]
+    pub created: bool,
+    #[serde(default)]
+    pub deleted: bool,
+    #[serde(default)]
+    pub forced: bool,
+}
+
+impl PushPayload {
+    fn format_message(&self, sender: &User, commit_limit: usize, flavor: MessageFlavor) -> String {
+        let branch = code(flavor, self.ref_name.trim_start_matches("refs/heads/"));
+
+        let header = if self.deleted {
+            format!("🗑️ {} deleted {}", bold(flavor, "Push"), branch)
+        } else if self.created {
+            format!("✨ {} created {}", bold(flavor, "Push"), branch)
+        } else if self.forced {
+            format!("⚠️ {} to {}", bold(flavor, "Force-push"), branch)
+        } else {
+            format!("⬆️ {} to {}", bold(flavor, "Push"), branch)
+        };
+
+        let mut lines = vec![header];
+
+        for commit in self.commits.iter().take(commit_limit) {
+            let short_sha = code(flavor, &commit.id[..commit.id.len().min(7)]);
+            let summary = truncate(commit.message.lines().next().unwrap_or(""), 72);
+            lines.push(format!(
+                "{} {} {} {}",
+                short_sha,
+                summary,
+                italic(flavor, &commit.author.name),
+                link(flavor, "↗", &commit.url)
+            ));
+        }
+
+        let remaining = self.commits.len().saturating_sub(commit_limit);
+        if remaining > 0 {
+            lines.push(format!("…and {} more", remaining));
+        }
+
+        lines.push(format!(
+            "{} • {} commit(s) • {}",
+            link(flavor, "Compare", &self.compare),
+            self.commits.len(),
+            italic(flavor, &link(flavor, &sender.login, &sender.html_url))
+        ));
+
+        lines.join("\n")
+    }
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        s.to_string()
+    } else {
+        let truncated: String = s.chars().take(max_chars.saturating_sub(1)).collect();
+        format!("{}…", truncated)
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -100,6 +203,8 @@ pub struct ReleasePayload {
     pub html_url: String,
     pub draft: bool,
     pub prerelease: bool,
+    /// The API URL for this release (distinct from `html_url`), used by `github::api` to enrich.
+    pub url: Option<String>,
 }
 
 impl GitHubEvent {
@@ -185,6 +290,16 @@ impl GitHubEvent {
         }
     }
 
+    /// The GitHub API URL to fetch for `enrich: true` routes, if this event type has one.
+    pub fn enrichment_url(&self) -> Option<&str> {
+        match &self.payload {
+            EventPayload::PullRequest(pr) => pr.url.as_deref(),
+            EventPayload::Issue(issue) => issue.url.as_deref(),
+            EventPayload::Release(release) => release.url.as_deref(),
+            EventPayload::Push(_) | EventPayload::WorkflowRun(_) | EventPayload::Unknown => None,
+        }
+    }
+
     pub fn event_key(&self) -> String {
         match &self.action {
             Some(action) => format!("{}.{}", self.event_type, action),
@@ -192,7 +307,67 @@ impl GitHubEvent {
         }
     }
 
-    pub fn format_message(&self) -> String {
+    /// Placeholder values available to user-defined templates, e.g. `{repo}` or `{pr.title}`.
+    pub fn template_context(&self) -> HashMap<String, String> {
+        let mut ctx = HashMap::new();
+        ctx.insert("repo".to_string(), self.repo.full_name.clone());
+        ctx.insert("repo_url".to_string(), self.repo.html_url.clone());
+        ctx.insert("sender".to_string(), self.sender.login.clone());
+        ctx.insert("sender_url".to_string(), self.sender.html_url.clone());
+        ctx.insert("event".to_string(), self.event_type.clone());
+        ctx.insert(
+            "action".to_string(),
+            self.action.clone().unwrap_or_default(),
+        );
+
+        match &self.payload {
+            EventPayload::PullRequest(pr) => {
+                ctx.insert("pr.number".to_string(), pr.number.to_string());
+                ctx.insert("pr.title".to_string(), pr.title.clone());
+                ctx.insert("pr.url".to_string(), pr.html_url.clone());
+                ctx.insert("pr.state".to_string(), pr.state.clone());
+                ctx.insert("branch".to_string(), pr.base.r#ref.clone());
+            }
+            EventPayload::Issue(issue) => {
+                ctx.insert("issue.number".to_string(), issue.number.to_string());
+                ctx.insert("issue.title".to_string(), issue.title.clone());
+                ctx.insert("issue.url".to_string(), issue.html_url.clone());
+                ctx.insert("issue.state".to_string(), issue.state.clone());
+            }
+            EventPayload::Push(push) => {
+                let branch = push.ref_name.trim_start_matches("refs/heads/");
+                ctx.insert("branch".to_string(), branch.to_string());
+                ctx.insert("compare".to_string(), push.compare.clone());
+                ctx.insert("commit_count".to_string(), push.commits.len().to_string());
+                ctx.insert("created".to_string(), push.created.to_string());
+                ctx.insert("deleted".to_string(), push.deleted.to_string());
+                ctx.insert("forced".to_string(), push.forced.to_string());
+            }
+            EventPayload::WorkflowRun(workflow) => {
+                ctx.insert("workflow.name".to_string(), workflow.name.clone());
+                ctx.insert("branch".to_string(), workflow.head_branch.clone());
+                ctx.insert("workflow.status".to_string(), workflow.status.clone());
+                ctx.insert(
+                    "workflow.conclusion".to_string(),
+                    workflow.conclusion.clone().unwrap_or_default(),
+                );
+                ctx.insert("workflow.url".to_string(), workflow.html_url.clone());
+            }
+            EventPayload::Release(release) => {
+                ctx.insert("release.tag".to_string(), release.tag_name.clone());
+                ctx.insert(
+                    "release.name".to_string(),
+                    release.name.clone().unwrap_or_else(|| release.tag_name.clone()),
+                );
+                ctx.insert("release.url".to_string(), release.html_url.clone());
+            }
+            EventPayload::Unknown => {}
+        }
+
+        ctx
+    }
+
+    pub fn format_message(&self, push_commit_limit: usize, flavor: MessageFlavor) -> String {
         match &self.payload {
             EventPayload::PullRequest(pr) => {
                 let action = self.action.as_deref().unwrap_or("updated");
@@ -205,15 +380,13 @@ impl GitHubEvent {
                     _ => "📝",
                 };
                 format!(
-                    "{} *Pull Request {}* [#{}]({})\n`{}` → {}\n_by [{}]({})_",
+                    "{} {} {}\n{} → {}\n{}",
                     emoji,
-                    action,
-                    pr.number,
-                    pr.html_url,
-                    pr.base.r#ref,
+                    bold(flavor, &format!("Pull Request {action}")),
+                    link(flavor, &format!("#{}", pr.number), &pr.html_url),
+                    code(flavor, &pr.base.r#ref),
                     pr.title,
-                    self.sender.login,
-                    self.sender.html_url
+                    italic(flavor, &format!("by {}", link(flavor, &self.sender.login, &self.sender.html_url)))
                 )
             }
             EventPayload::Issue(issue) => {
@@ -225,24 +398,15 @@ impl GitHubEvent {
                     _ => "📋",
                 };
                 format!(
-                    "{} *Issue {}* [#{}]({})\n{}\n_by [{}]({})_",
+                    "{} {} {}\n{}\n{}",
                     emoji,
-                    action,
-                    issue.number,
-                    issue.html_url,
+                    bold(flavor, &format!("Issue {action}")),
+                    link(flavor, &format!("#{}", issue.number), &issue.html_url),
                     issue.title,
-                    self.sender.login,
-                    self.sender.html_url
-                )
-            }
-            EventPayload::Push(push) => {
-                let branch = push.ref_name.trim_start_matches("refs/heads/");
-                let commits = push.commits.len();
-                format!(
-                    "⬆️ *Push* to `{}`\n[Compare]({}) • {} commit(s)\n_by [{}]({})_",
-                    branch, push.compare, commits, self.sender.login, self.sender.html_url
+                    italic(flavor, &format!("by {}", link(flavor, &self.sender.login, &self.sender.html_url)))
                 )
             }
+            EventPayload::Push(push) => push.format_message(&self.sender, push_commit_limit, flavor),
             EventPayload::WorkflowRun(workflow) => {
                 let emoji = match workflow.conclusion.as_deref() {
                     Some("success") => "✅",
@@ -251,12 +415,13 @@ impl GitHubEvent {
                     _ => "⏳",
                 };
                 format!(
-                    "{} *Workflow* `{}`\nBranch: `{}` • Status: {}\n[View Run]({})",
+                    "{} {} {}\nBranch: {} • Status: {}\n{}",
                     emoji,
-                    workflow.name,
-                    workflow.head_branch,
+                    bold(flavor, "Workflow"),
+                    code(flavor, &workflow.name),
+                    code(flavor, &workflow.head_branch),
                     workflow.conclusion.as_deref().unwrap_or(&workflow.status),
-                    workflow.html_url
+                    link(flavor, "View Run", &workflow.html_url)
                 )
             }
             EventPayload::Release(release) => {
@@ -268,19 +433,21 @@ impl GitHubEvent {
                     "🏷️"
                 };
                 format!(
-                    "{} *Release* `{}`\n{}\n[View Release]({})\n_by [{}]({})_",
+                    "{} {} {}\n{}\n{}\n{}",
                     emoji,
-                    release.tag_name,
+                    bold(flavor, "Release"),
+                    code(flavor, &release.tag_name),
                     release.name.as_deref().unwrap_or(&release.tag_name),
-                    release.html_url,
-                    self.sender.login,
-                    self.sender.html_url
+                    link(flavor, "View Release", &release.html_url),
+                    italic(flavor, &format!("by {}", link(flavor, &self.sender.login, &self.sender.html_url)))
                 )
             }
             EventPayload::Unknown => {
                 format!(
-                    "📡 *{}* on `{}`\n_by [{}]({})_",
-                    self.event_type, self.repo.full_name, self.sender.login, self.sender.html_url
+                    "📡 {} on {}\n{}",
+                    bold(flavor, &self.event_type),
+                    code(flavor, &self.repo.full_name),
+                    italic(flavor, &format!("by {}", link(flavor, &self.sender.login, &self.sender.html_url)))
                 )
             }
         }
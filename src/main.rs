@@ -1,20 +1,34 @@
+mod bot;
 mod config;
 mod github;
+mod notifier;
+mod queue;
+mod routes;
 mod telegram;
 mod webhook;
 
 use axum::{routing::{get, post}, Router};
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+use crate::bot::AdminNotifier;
 use crate::config::Settings;
-use crate::telegram::TelegramClient;
+use crate::github::api::ApiClient;
+use crate::notifier::{CompiledRoute, Registry};
+use crate::queue::Queue;
+use crate::routes::RouteStore;
 use crate::webhook::{handle_webhook, health_check};
 
 #[derive(Clone)]
 pub struct AppState {
     settings: Settings,
-    telegram: TelegramClient,
+    queue: Arc<Queue>,
+    api_client: Arc<ApiClient>,
+    compiled_routes: Arc<Vec<CompiledRoute>>,
+    route_store: Arc<RouteStore>,
+    admin_notifier: Option<AdminNotifier>,
 }
 
 #[tokio::main]
@@ -30,11 +44,38 @@ async fn main() -> anyhow::Result<()> {
     let settings = Settings::load()?;
     let addr: SocketAddr = format!("{}:{}", settings.server.host, settings.server.port).parse()?;
     
-    let telegram = TelegramClient::new(settings.telegram.bot_token.clone());
-    
+    let notifiers = Arc::new(Registry::new(&settings.telegram));
+    let queue = Arc::new(Queue::open(&settings.queue.db_path)?);
+    let api_client = Arc::new(ApiClient::new(
+        settings.github.api_token.clone(),
+        Duration::from_secs(settings.github.enrich_cache_ttl_secs),
+    ));
+    let compiled_routes = Arc::new(notifier::compile_routes(&settings.routing)?);
+    let route_store = Arc::new(RouteStore::open(&settings.queue.db_path)?);
+
+    tokio::spawn(queue::run_worker(queue.clone(), notifiers));
+
+    if settings.telegram.admin_chat_id.is_some() || settings.telegram.admin_handle.is_some() {
+        tokio::spawn(bot::run_bot(
+            settings.telegram.bot_token.clone(),
+            settings.telegram.admin_chat_id,
+            settings.telegram.admin_handle.clone(),
+            route_store.clone(),
+        ));
+    }
+
+    let admin_notifier = settings
+        .telegram
+        .admin_chat_id
+        .map(|chat_id| AdminNotifier::new(settings.telegram.bot_token.clone(), chat_id));
+
     let state = AppState {
         settings,
-        telegram,
+        queue,
+        api_client,
+        compiled_routes,
+        route_store,
+        admin_notifier,
     };
 
     let app = Router::new()
@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::Client;
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use crate::config::patterns::{compile_event_pattern, compile_repo_pattern};
+use crate::config::{GitHubConfig, NotifyTarget, RouteConfig, TelegramConfig};
+use crate::github::api::{self, ApiClient};
+use crate::github::{GitHubEvent, MessageFlavor};
+use crate::telegram::{template, ParseMode, TelegramClient};
+
+/// A backend capable of delivering an already-rendered message to one of its targets.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn send(&self, target: &NotifyTarget, message: &str) -> anyhow::Result<()>;
+}
+
+struct TelegramNotifier {
+    client: TelegramClient,
+    parse_mode: ParseMode,
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    async fn send(&self, target: &NotifyTarget, message: &str) -> anyhow::Result<()> {
+        let NotifyTarget::Telegram { chat_id, parse_mode } = target else {
+            anyhow::bail!("TelegramNotifier received a non-Telegram target");
+        };
+        let parse_mode = parse_mode.unwrap_or(self.parse_mode);
+        self.client.send_message(*chat_id, message, parse_mode).await
+    }
+}
+
+struct SlackNotifier {
+    client: Client,
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    async fn send(&self, target: &NotifyTarget, message: &str) -> anyhow::Result<()> {
+        let NotifyTarget::Slack { webhook_url, channel } = target else {
+            anyhow::bail!("SlackNotifier received a non-Slack target");
+        };
+
+        let mut payload = json!({
+            "blocks": [{
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": message }
+            }]
+        });
+        if let Some(channel) = channel {
+            payload["channel"] = json!(channel);
+        }
+
+        post_webhook(&self.client, webhook_url, &payload).await
+    }
+}
+
+struct DiscordNotifier {
+    client: Client,
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    async fn send(&self, target: &NotifyTarget, message: &str) -> anyhow::Result<()> {
+        let NotifyTarget::Discord { webhook_url } = target else {
+            anyhow::bail!("DiscordNotifier received a non-Discord target");
+        };
+
+        let payload = json!({ "embeds": [{ "description": message }] });
+        post_webhook(&self.client, webhook_url, &payload).await
+    }
+}
+
+struct GenericNotifier {
+    client: Client,
+}
+
+#[async_trait]
+impl Notifier for GenericNotifier {
+    async fn send(&self, target: &NotifyTarget, message: &str) -> anyhow::Result<()> {
+        let NotifyTarget::Generic { url } = target else {
+            anyhow::bail!("GenericNotifier received a non-Generic target");
+        };
+
+        post_webhook(&self.client, url, &json!({ "message": message })).await
+    }
+}
+
+async fn post_webhook(client: &Client, url: &str, payload: &Value) -> anyhow::Result<()> {
+    let response = client.post(url).json(payload).send().await?;
+
+    if !response.status().is_success() {
+        let body = response.text().await?;
+        anyhow::bail!("webhook POST to {} failed: {}", url, body);
+    }
+
+    Ok(())
+}
+
+/// One notifier instance per backend kind, built once at startup and shared across requests.
+pub struct Registry {
+    notifiers: HashMap<&'static str, Box<dyn Notifier>>,
+}
+
+impl Registry {
+    pub fn new(telegram_config: &TelegramConfig) -> Self {
+        let http = Client::new();
+        let mut notifiers: HashMap<&'static str, Box<dyn Notifier>> = HashMap::new();
+
+        notifiers.insert(
+            "telegram",
+            Box::new(TelegramNotifier {
+                client: TelegramClient::new(telegram_config.bot_token.clone()),
+                parse_mode: telegram_config.parse_mode,
+            }),
+        );
+        notifiers.insert("slack", Box::new(SlackNotifier { client: http.clone() }));
+        notifiers.insert("discord", Box::new(DiscordNotifier { client: http.clone() }));
+        notifiers.insert("generic", Box::new(GenericNotifier { client: http }));
+
+        Self { notifiers }
+    }
+
+    /// Dispatches an already-rendered message to the notifier backend for `target.kind()`.
+    pub async fn send(&self, target: &NotifyTarget, message: &str) -> anyhow::Result<()> {
+        let kind = target.kind();
+        let Some(notifier) = self.notifiers.get(kind) else {
+            anyhow::bail!("no notifier registered for target kind {kind}");
+        };
+        notifier.send(target, message).await
+    }
+}
+
+#[derive(Clone)]
+struct CompiledPattern {
+    regex: Regex,
+    negate: bool,
+}
+
+/// A `RouteConfig` with its `repo_pattern`/`events` compiled once, so matching a route
+/// against an event never recompiles a regex on the request path. `Clone` is cheap
+/// (`Regex` is internally reference-counted) and lets callers merge the statically
+/// compiled routes with ones read live from `routes::RouteStore`.
+#[derive(Clone)]
+pub struct CompiledRoute {
+    route: RouteConfig,
+    repo_pattern: CompiledPattern,
+    event_patterns: Vec<CompiledPattern>,
+}
+
+/// Compiles every route's patterns up front. Mirrors `Settings::validate_routing`'s
+/// checks, so this should never fail for a `Settings` that already loaded successfully.
+pub fn compile_routes(routes: &[RouteConfig]) -> anyhow::Result<Vec<CompiledRoute>> {
+    routes
+        .iter()
+        .map(|route| {
+            let (regex, negate) = compile_repo_pattern(&route.repo_pattern)?;
+            let repo_pattern = CompiledPattern { regex, negate };
+
+            let event_patterns = route
+                .events
+                .iter()
+                .map(|pattern| {
+                    let (regex, negate) = compile_event_pattern(pattern)?;
+                    Ok(CompiledPattern { regex, negate })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            Ok(CompiledRoute {
+                route: route.clone(),
+                repo_pattern,
+                event_patterns,
+            })
+        })
+        .collect()
+}
+
+/// Like `compile_routes`, but for routes read live from `routes::RouteStore`: skips and
+/// warns on a row that fails to compile instead of failing the whole batch, so one bad
+/// `/subscribe` row can't take down every other chat's subscriptions.
+pub fn compile_routes_lenient(routes: &[RouteConfig]) -> Vec<CompiledRoute> {
+    routes
+        .iter()
+        .filter_map(|route| match compile_routes(std::slice::from_ref(route)) {
+            Ok(mut compiled) => compiled.pop(),
+            Err(e) => {
+                warn!(
+                    repo_pattern = %route.repo_pattern,
+                    error = %e,
+                    "Skipping dynamic route with invalid pattern"
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+/// Matches `event` against `routes` and renders the message each matching route should
+/// receive, enriching the template context from the GitHub API for routes with
+/// `enrich: true`. Delivery itself is left to the caller (the durable queue enqueues
+/// these; `Registry::send` delivers them).
+pub async fn resolve_deliveries(
+    telegram_config: &TelegramConfig,
+    github_config: &GitHubConfig,
+    api_client: &ApiClient,
+    routes: &[CompiledRoute],
+    event: &GitHubEvent,
+) -> Vec<(NotifyTarget, String)> {
+    let event_key = event.event_key();
+    let mut deliveries = Vec::new();
+    let mut enrichment: Option<HashMap<String, String>> = None;
+
+    for compiled in routes {
+        let route = &compiled.route;
+
+        if !matches_repo(&compiled.repo_pattern, &event.repo.full_name) {
+            continue;
+        }
+
+        if !matches_event(&compiled.event_patterns, &event_key, &event.event_type) {
+            continue;
+        }
+
+        // Telegram's Markdown/MarkdownV2/HTML escape syntax is meaningless to the other
+        // backends, so only escape interpolated values when this route actually
+        // delivers to Telegram.
+        let parse_mode = match route.target {
+            NotifyTarget::Telegram { .. } => {
+                Some(route.parse_mode.unwrap_or(telegram_config.parse_mode))
+            }
+            _ => None,
+        };
+        let message = match route
+            .templates
+            .get(&event_key)
+            .or_else(|| telegram_config.templates.get(&event_key))
+        {
+            Some(tpl) => {
+                let mut ctx = event.template_context();
+                if route.enrich {
+                    if enrichment.is_none() {
+                        enrichment = Some(fetch_enrichment(api_client, event).await);
+                    }
+                    ctx.extend(enrichment.clone().unwrap_or_default());
+                }
+                template::render(tpl, &ctx, parse_mode)
+            }
+            None => {
+                let flavor = match route.target {
+                    NotifyTarget::Telegram { .. } => MessageFlavor::TelegramMarkdown,
+                    _ => MessageFlavor::PlainText,
+                };
+                event.format_message(github_config.push_commit_limit, flavor)
+            }
+        };
+
+        info!(
+            repo = %event.repo.full_name,
+            target = %route.target.kind(),
+            event = %event_key,
+            "Matched route"
+        );
+
+        // `Registry`'s `TelegramNotifier` is built once at startup with the global
+        // default parse_mode baked in, so the per-route override has to ride along on
+        // the target itself (rather than being lost after only affecting escaping
+        // above) for it to actually reach `sendMessage`.
+        let target = match &route.target {
+            NotifyTarget::Telegram { chat_id, .. } => NotifyTarget::Telegram {
+                chat_id: *chat_id,
+                parse_mode,
+            },
+            other => other.clone(),
+        };
+
+        deliveries.push((target, message));
+    }
+
+    deliveries
+}
+
+async fn fetch_enrichment(api_client: &ApiClient, event: &GitHubEvent) -> HashMap<String, String> {
+    let Some(url) = event.enrichment_url() else {
+        return HashMap::new();
+    };
+
+    match api_client.get_json(url).await {
+        Ok(value) => api::flatten_for_template(&value),
+        Err(e) => {
+            tracing::warn!(error = %e, url = %url, "Failed to enrich event from GitHub API");
+            HashMap::new()
+        }
+    }
+}
+
+fn matches_repo(pattern: &CompiledPattern, repo_name: &str) -> bool {
+    pattern.regex.is_match(repo_name) != pattern.negate
+}
+
+/// A route matches if at least one non-negated pattern matches the event and no
+/// negated pattern does — so `["*", "!push"]` means "everything except push".
+fn matches_event(patterns: &[CompiledPattern], event_key: &str, event_type: &str) -> bool {
+    let mut matched_positive = false;
+
+    for pattern in patterns {
+        let is_match = pattern.regex.is_match(event_key) || pattern.regex.is_match(event_type);
+        if pattern.negate {
+            if is_match {
+                return false;
+            }
+        } else if is_match {
+            matched_positive = true;
+        }
+    }
+
+    matched_positive
+}
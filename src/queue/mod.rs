@@ -0,0 +1,178 @@
+mod worker;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rusqlite::Connection;
+use tokio::sync::Mutex;
+
+use crate::config::NotifyTarget;
+
+pub use worker::run_worker;
+
+const MAX_ATTEMPTS: i64 = 8;
+const BASE_BACKOFF_SECS: i64 = 5;
+const MAX_BACKOFF_SECS: i64 = 900;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JobState {
+    Pending,
+    Sent,
+    Failed,
+}
+
+impl JobState {
+    fn as_str(self) -> &'static str {
+        match self {
+            JobState::Pending => "pending",
+            JobState::Sent => "sent",
+            JobState::Failed => "failed",
+        }
+    }
+}
+
+pub struct QueuedNotification {
+    pub id: i64,
+    pub target: NotifyTarget,
+    pub message: String,
+    pub attempts: i64,
+}
+
+/// SQLite-backed outbound notification queue. Rows survive process restarts; a background
+/// worker (see [`run_worker`]) polls due rows and retries with exponential backoff.
+pub struct Queue {
+    conn: Mutex<Connection>,
+}
+
+impl Queue {
+    pub fn open(db_path: &str) -> anyhow::Result<Self> {
+        let conn = Connection::open(db_path)?;
+        // `routes::RouteStore` opens its own connection to the same file and is read on
+        // every webhook request while this one is written every `run_worker` poll; WAL
+        // lets readers and the writer proceed concurrently, and the busy timeout covers
+        // the remaining writer/writer contention instead of failing fast with
+        // `SQLITE_BUSY`.
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS notifications (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                target_kind TEXT NOT NULL,
+                target_json TEXT NOT NULL,
+                message TEXT NOT NULL,
+                state TEXT NOT NULL DEFAULT 'pending',
+                attempts INTEGER NOT NULL DEFAULT 0,
+                next_attempt_at INTEGER NOT NULL,
+                last_error TEXT,
+                created_at INTEGER NOT NULL
+            );",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    pub async fn enqueue(&self, target: &NotifyTarget, message: &str) -> anyhow::Result<()> {
+        let target_json = serde_json::to_string(target)?;
+        let now = now_unix();
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO notifications
+                (target_kind, target_json, message, state, attempts, next_attempt_at, created_at)
+             VALUES (?1, ?2, ?3, ?4, 0, ?5, ?5)",
+            rusqlite::params![target.kind(), target_json, message, JobState::Pending.as_str(), now],
+        )?;
+        Ok(())
+    }
+
+    /// Pending rows whose `next_attempt_at` has passed, oldest first, capped at `limit`.
+    pub async fn due(&self, limit: i64) -> anyhow::Result<Vec<QueuedNotification>> {
+        let now = now_unix();
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT id, target_json, message, attempts FROM notifications
+             WHERE state = ?1 AND next_attempt_at <= ?2
+             ORDER BY next_attempt_at ASC LIMIT ?3",
+        )?;
+        let rows = stmt
+            .query_map(
+                rusqlite::params![JobState::Pending.as_str(), now, limit],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, i64>(3)?,
+                    ))
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        rows.into_iter()
+            .map(|(id, target_json, message, attempts)| {
+                let target: NotifyTarget = serde_json::from_str(&target_json)?;
+                Ok(QueuedNotification {
+                    id,
+                    target,
+                    message,
+                    attempts,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn mark_sent(&self, id: i64) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "UPDATE notifications SET state = ?2 WHERE id = ?1",
+            rusqlite::params![id, JobState::Sent.as_str()],
+        )?;
+        Ok(())
+    }
+
+    pub async fn mark_retry(&self, id: i64, delay_secs: i64, error: &str) -> anyhow::Result<()> {
+        let next_attempt_at = now_unix() + delay_secs;
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "UPDATE notifications SET attempts = attempts + 1, next_attempt_at = ?2, last_error = ?3
+             WHERE id = ?1",
+            rusqlite::params![id, next_attempt_at, error],
+        )?;
+        Ok(())
+    }
+
+    pub async fn mark_failed(&self, id: i64, error: &str) -> anyhow::Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "UPDATE notifications SET state = ?2, attempts = attempts + 1, last_error = ?3
+             WHERE id = ?1",
+            rusqlite::params![id, JobState::Failed.as_str(), error],
+        )?;
+        Ok(())
+    }
+
+    /// Number of rows still awaiting delivery, surfaced on `/health`.
+    pub async fn pending_depth(&self) -> anyhow::Result<i64> {
+        let conn = self.conn.lock().await;
+        let depth = conn.query_row(
+            "SELECT COUNT(*) FROM notifications WHERE state = ?1",
+            rusqlite::params![JobState::Pending.as_str()],
+            |row| row.get(0),
+        )?;
+        Ok(depth)
+    }
+}
+
+fn now_unix() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+/// Exponential backoff for the given attempt count, capped at `MAX_BACKOFF_SECS`.
+fn backoff_secs(attempts: i64) -> i64 {
+    let shift = attempts.clamp(0, 20) as u32;
+    BASE_BACKOFF_SECS
+        .saturating_mul(1i64.checked_shl(shift).unwrap_or(i64::MAX))
+        .min(MAX_BACKOFF_SECS)
+}
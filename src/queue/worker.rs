@@ -0,0 +1,51 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{error, info, warn};
+
+use crate::notifier::Registry;
+use crate::telegram::RateLimited;
+
+use super::{backoff_secs, Queue, MAX_ATTEMPTS};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const BATCH_SIZE: i64 = 25;
+
+/// Polls `queue` for due rows and delivers them through `registry`, retrying with
+/// backoff (or Telegram's `retry_after` when rate-limited) until `MAX_ATTEMPTS` is hit.
+pub async fn run_worker(queue: Arc<Queue>, registry: Arc<Registry>) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = poll_once(&queue, &registry).await {
+            error!(error = %e, "Queue poll failed");
+        }
+    }
+}
+
+async fn poll_once(queue: &Queue, registry: &Registry) -> anyhow::Result<()> {
+    for item in queue.due(BATCH_SIZE).await? {
+        match registry.send(&item.target, &item.message).await {
+            Ok(()) => {
+                queue.mark_sent(item.id).await?;
+                info!(id = item.id, "Notification delivered");
+            }
+            Err(e) => {
+                let retry_after_secs = e
+                    .downcast_ref::<RateLimited>()
+                    .map(|r| r.retry_after_secs as i64);
+                let delay = retry_after_secs.unwrap_or_else(|| backoff_secs(item.attempts));
+
+                if item.attempts + 1 >= MAX_ATTEMPTS {
+                    queue.mark_failed(item.id, &e.to_string()).await?;
+                    warn!(id = item.id, error = %e, "Notification permanently failed");
+                } else {
+                    queue.mark_retry(item.id, delay, &e.to_string()).await?;
+                    warn!(id = item.id, error = %e, delay, "Notification retry scheduled");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use rusqlite::Connection;
+use tokio::sync::Mutex;
+
+use crate::config::patterns::{compile_event_pattern, compile_repo_pattern};
+use crate::config::{NotifyTarget, RouteConfig};
+use crate::notifier::{compile_routes_lenient, CompiledRoute};
+
+/// SQLite-backed routing table that the control bot (see `crate::bot`) mutates at
+/// runtime, independent of the static `routing` list in `config`. Rows are merged with
+/// the statically compiled routes before each webhook is matched, so a `/subscribe`
+/// takes effect on the very next delivery.
+pub struct RouteStore {
+    conn: Mutex<Connection>,
+}
+
+impl RouteStore {
+    pub fn open(db_path: &str) -> anyhow::Result<Self> {
+        let conn = Connection::open(db_path)?;
+        // Shares its db file with `queue::Queue`; WAL plus a busy timeout lets this
+        // connection's per-request reads and that one's poll-driven writes coexist
+        // instead of racing into `SQLITE_BUSY`.
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS dynamic_routes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                repo_pattern TEXT NOT NULL,
+                events_json TEXT NOT NULL,
+                target_json TEXT NOT NULL,
+                muted INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE UNIQUE INDEX IF NOT EXISTS dynamic_routes_unique
+                ON dynamic_routes (repo_pattern, events_json, target_json);
+            CREATE TABLE IF NOT EXISTS prompted_repos (
+                repo_full_name TEXT PRIMARY KEY
+            );",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    pub async fn subscribe(
+        &self,
+        repo_pattern: &str,
+        events: &[String],
+        target: &NotifyTarget,
+    ) -> anyhow::Result<()> {
+        // Reject an unparseable pattern up front, same as `Settings::validate_routing`
+        // does for the static config — otherwise a typo'd `/subscribe` would compile
+        // fine into the DB and only surface as a broken row later.
+        compile_repo_pattern(repo_pattern)?;
+        for event in events {
+            compile_event_pattern(event)?;
+        }
+
+        let events_json = serde_json::to_string(events)?;
+        let target_json = serde_json::to_string(target)?;
+        let conn = self.conn.lock().await;
+        // `dynamic_routes_unique` makes a repeat `/subscribe` (or a double-tap on the
+        // inline "Subscribe" button) idempotent instead of inserting a second row that
+        // would double-deliver every future event for this repo.
+        conn.execute(
+            "INSERT INTO dynamic_routes (repo_pattern, events_json, target_json, muted)
+             VALUES (?1, ?2, ?3, 0)
+             ON CONFLICT (repo_pattern, events_json, target_json) DO UPDATE SET muted = 0",
+            rusqlite::params![repo_pattern, events_json, target_json],
+        )?;
+        // A repo that gets subscribed is no longer "unrouted", so forget that it was
+        // prompted — if it's later unsubscribed, `mark_prompted` can fire again.
+        conn.execute(
+            "DELETE FROM prompted_repos WHERE repo_full_name = ?1",
+            rusqlite::params![repo_pattern],
+        )?;
+        Ok(())
+    }
+
+    /// Removes every subscription matching `repo_pattern` exactly, returning how many
+    /// rows were deleted.
+    pub async fn unsubscribe(&self, repo_pattern: &str) -> anyhow::Result<usize> {
+        let conn = self.conn.lock().await;
+        let affected = conn.execute(
+            "DELETE FROM dynamic_routes WHERE repo_pattern = ?1",
+            rusqlite::params![repo_pattern],
+        )?;
+        Ok(affected)
+    }
+
+    /// Mutes (rather than deletes) every subscription matching `repo_pattern` exactly.
+    /// A muted row is reactivated only by a `/subscribe` with the same event list and
+    /// target; any other `/subscribe` for the pattern inserts a new row.
+    pub async fn mute(&self, repo_pattern: &str) -> anyhow::Result<usize> {
+        let conn = self.conn.lock().await;
+        let affected = conn.execute(
+            "UPDATE dynamic_routes SET muted = 1 WHERE repo_pattern = ?1",
+            rusqlite::params![repo_pattern],
+        )?;
+        Ok(affected)
+    }
+
+    /// Active (unmuted) dynamic routes, as plain `RouteConfig`s.
+    pub async fn list(&self) -> anyhow::Result<Vec<RouteConfig>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT repo_pattern, events_json, target_json FROM dynamic_routes WHERE muted = 0",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        rows.into_iter()
+            .map(|(repo_pattern, events_json, target_json)| {
+                let events: Vec<String> = serde_json::from_str(&events_json)?;
+                let target: NotifyTarget = serde_json::from_str(&target_json)?;
+                Ok(RouteConfig {
+                    repo_pattern,
+                    target,
+                    events,
+                    parse_mode: None,
+                    templates: Default::default(),
+                    enrich: false,
+                })
+            })
+            .collect()
+    }
+
+    /// Records that `repo_full_name` was just sent an auto-registration DM. Returns
+    /// `true` the first time a given repo is recorded, `false` if it was already
+    /// prompted, so `webhook::handle_webhook` only DMs the admin once per unrouted repo
+    /// instead of once per event.
+    pub async fn mark_prompted(&self, repo_full_name: &str) -> anyhow::Result<bool> {
+        let conn = self.conn.lock().await;
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO prompted_repos (repo_full_name) VALUES (?1)",
+            rusqlite::params![repo_full_name],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// Compiles the current dynamic routes. Uses `compile_routes_lenient` rather than
+    /// `notifier::compile_routes`: `subscribe()` already rejects invalid patterns, but a
+    /// bad row could still reach the table by other means, and one bad row shouldn't
+    /// drop every other chat's subscriptions for the request.
+    pub async fn compiled_routes(&self) -> anyhow::Result<Vec<CompiledRoute>> {
+        Ok(compile_routes_lenient(&self.list().await?))
+    }
+}
@@ -1,9 +1,42 @@
+pub mod template;
+
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tracing::{info, warn};
+use tracing::info;
 
-use crate::config::RouteConfig;
-use crate::github::GitHubEvent;
+/// Telegram message formatting mode, as accepted by the `sendMessage` `parse_mode` field.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    Markdown,
+    MarkdownV2,
+    #[serde(rename = "HTML")]
+    Html,
+}
+
+impl ParseMode {
+    fn as_telegram_str(self) -> &'static str {
+        match self {
+            ParseMode::Markdown => "Markdown",
+            ParseMode::MarkdownV2 => "MarkdownV2",
+            ParseMode::Html => "HTML",
+        }
+    }
+}
+
+/// Telegram answered 429 Too Many Requests; callers should retry after `retry_after_secs`.
+#[derive(Debug)]
+pub struct RateLimited {
+    pub retry_after_secs: u64,
+}
+
+impl std::fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rate limited by Telegram, retry after {}s", self.retry_after_secs)
+    }
+}
+
+impl std::error::Error for RateLimited {}
 
 #[derive(Clone)]
 pub struct TelegramClient {
@@ -19,21 +52,36 @@ impl TelegramClient {
         }
     }
 
-    pub async fn send_message(&self, chat_id: i64, text: &str) -> anyhow::Result<()> {
+    pub async fn send_message(
+        &self,
+        chat_id: i64,
+        text: &str,
+        parse_mode: ParseMode,
+    ) -> anyhow::Result<()> {
         let url = format!("https://api.telegram.org/bot{}/sendMessage", self.bot_token);
-        
+
         let response = self
             .client
             .post(&url)
             .json(&json!({
                 "chat_id": chat_id,
                 "text": text,
-                "parse_mode": "Markdown",
+                "parse_mode": parse_mode.as_telegram_str(),
                 "disable_web_page_preview": false
             }))
             .send()
             .await?;
 
+        if response.status().as_u16() == 429 {
+            let body: serde_json::Value = response.json().await.unwrap_or_default();
+            let retry_after_secs = body
+                .get("parameters")
+                .and_then(|p| p.get("retry_after"))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(1);
+            return Err(anyhow::Error::new(RateLimited { retry_after_secs }));
+        }
+
         if !response.status().is_success() {
             let body = response.text().await?;
             anyhow::bail!("Telegram API error: {}", body);
@@ -42,55 +90,4 @@ impl TelegramClient {
         info!(chat_id = %chat_id, "Message sent to Telegram");
         Ok(())
     }
-
-    pub async fn send_event_notification(
-        &self,
-        routes: &[RouteConfig],
-        event: &GitHubEvent,
-    ) -> anyhow::Result<()> {
-        let event_key = event.event_key();
-        let message = event.format_message();
-
-        for route in routes {
-            if !matches_repo(&route.repo_pattern, &event.repo.full_name) {
-                continue;
-            }
-
-            if !matches_event(&route.events, &event_key, &event.event_type) {
-                continue;
-            }
-
-            info!(
-                repo = %event.repo.full_name,
-                chat_id = %route.chat_id,
-                event = %event_key,
-                "Routing event"
-            );
-
-            if let Err(e) = self.send_message(route.chat_id, &message).await {
-                warn!(error = %e, chat_id = %route.chat_id, "Failed to send message");
-            }
-        }
-
-        Ok(())
-    }
-}
-
-fn matches_repo(pattern: &str, repo_name: &str) -> bool {
-    if pattern == "*" {
-        return true;
-    }
-    
-    if pattern.contains('*') {
-        let prefix = pattern.trim_end_matches('*');
-        repo_name.starts_with(prefix)
-    } else {
-        repo_name == pattern
-    }
-}
-
-fn matches_event(subscribed: &[String], event_key: &str, event_type: &str) -> bool {
-    subscribed.iter().any(|s| {
-        s == "*" || s == event_key || s == event_type
-    })
 }
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use super::ParseMode;
+
+/// Renders a user-defined template, substituting `{placeholder}` values from `context`.
+/// `parse_mode` is Telegram-specific escape syntax (Markdown/MarkdownV2/HTML); pass
+/// `None` for backends that don't interpret it (Slack, Discord, generic webhooks) so
+/// values are substituted verbatim instead of growing stray backslashes.
+pub fn render(
+    template: &str,
+    context: &HashMap<String, String>,
+    parse_mode: Option<ParseMode>,
+) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '{' {
+            out.push(c);
+            continue;
+        }
+
+        let mut key = String::new();
+        let mut closed = false;
+        while let Some(&next) = chars.peek() {
+            if next == '}' {
+                chars.next();
+                closed = true;
+                break;
+            }
+            key.push(next);
+            chars.next();
+        }
+
+        if closed {
+            if let Some(value) = context.get(&key) {
+                match parse_mode {
+                    Some(parse_mode) => out.push_str(&escape(value, parse_mode)),
+                    None => out.push_str(value),
+                }
+                continue;
+            }
+        }
+
+        out.push('{');
+        out.push_str(&key);
+        if closed {
+            out.push('}');
+        }
+    }
+
+    out
+}
+
+/// Escapes a value for safe interpolation into a message of the given `parse_mode`.
+pub fn escape(value: &str, parse_mode: ParseMode) -> String {
+    match parse_mode {
+        ParseMode::MarkdownV2 => escape_markdown_v2(value),
+        ParseMode::Html => escape_html(value),
+        ParseMode::Markdown => escape_markdown_legacy(value),
+    }
+}
+
+fn escape_markdown_v2(value: &str) -> String {
+    const SPECIAL: &[char] = &[
+        '_', '*', '[', ']', '(', ')', '~', '`', '>', '#', '+', '-', '=', '|', '{', '}', '.', '!',
+    ];
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if SPECIAL.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn escape_markdown_legacy(value: &str) -> String {
+    const SPECIAL: &[char] = &['_', '*', '[', '`'];
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if SPECIAL.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -3,12 +3,16 @@ use axum::{
     extract::State,
     http::{HeaderMap, StatusCode},
     response::IntoResponse,
+    Json,
 };
 use hmac::{Hmac, Mac};
+use serde_json::json;
 use sha2::Sha256;
 use tracing::{error, info, warn};
 
+use crate::config::WebhookSecrets;
 use crate::github::GitHubEvent;
+use crate::notifier;
 use crate::AppState;
 
 type HmacSha256 = Hmac<Sha256>;
@@ -48,31 +52,73 @@ pub async fn handle_webhook(
         }
     };
 
-    if let Err(e) = state.telegram.send_event_notification(&state.settings.routing, &event).await {
-        error!(error = %e, "Failed to send notification");
-        return StatusCode::INTERNAL_SERVER_ERROR;
+    // Dynamic subscriptions (managed via `crate::bot`) live in `RouteStore` and can
+    // change between requests, so they're read and recompiled fresh here rather than
+    // cached on `AppState` like the static `compiled_routes`.
+    let mut routes = (*state.compiled_routes).clone();
+    match state.route_store.compiled_routes().await {
+        Ok(dynamic) => routes.extend(dynamic),
+        Err(e) => error!(error = %e, "Failed to load dynamic routes"),
+    }
+
+    let deliveries = notifier::resolve_deliveries(
+        &state.settings.telegram,
+        &state.settings.github,
+        &state.api_client,
+        &routes,
+        &event,
+    )
+    .await;
+
+    if deliveries.is_empty() {
+        if let Some(admin) = state.admin_notifier.clone() {
+            // Only DM once per unrouted repo, not once per event it sends.
+            match state.route_store.mark_prompted(&event.repo.full_name).await {
+                Ok(true) => {
+                    let repo_full_name = event.repo.full_name.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = admin.prompt_subscribe(&repo_full_name).await {
+                            error!(error = %e, "Failed to DM admin about unrouted repo");
+                        }
+                    });
+                }
+                Ok(false) => {}
+                Err(e) => error!(error = %e, "Failed to record prompted repo"),
+            }
+        }
+    }
+
+    for (target, message) in &deliveries {
+        if let Err(e) = state.queue.enqueue(target, message).await {
+            error!(error = %e, "Failed to enqueue notification");
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
     }
 
     StatusCode::OK
 }
 
-fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+fn verify_signature(secrets: &WebhookSecrets, body: &[u8], signature: &str) -> bool {
     let Ok(expected_sig) = hex::decode(signature) else {
         return false;
     };
 
-    let mut mac = match HmacSha256::new_from_slice(secret.as_bytes()) {
-        Ok(m) => m,
-        Err(_) => return false,
-    };
-
-    mac.update(body);
-    let result = mac.finalize();
-    let actual_sig = result.into_bytes();
+    secrets.as_slice().into_iter().any(|secret| {
+        let Ok(mut mac) = HmacSha256::new_from_slice(secret.as_bytes()) else {
+            return false;
+        };
 
-    expected_sig.as_slice() == actual_sig.as_slice()
+        mac.update(body);
+        mac.verify_slice(&expected_sig).is_ok()
+    })
 }
 
-pub async fn health_check() -> impl IntoResponse {
-    (StatusCode::OK, "OK")
+pub async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
+    match state.queue.pending_depth().await {
+        Ok(depth) => Json(json!({ "status": "ok", "queue_depth": depth })).into_response(),
+        Err(e) => {
+            error!(error = %e, "Failed to read queue depth");
+            (StatusCode::OK, "OK").into_response()
+        }
+    }
 }